@@ -0,0 +1,57 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::treewalk::Value;
+use crate::utils::Error;
+
+pub trait Builtin {
+    fn name(&self) -> &str;
+    fn arity(&self) -> usize;
+    fn call(&self, args: Vec<Value>) -> Result<Value, Error>;
+}
+
+pub struct Clock;
+
+impl Builtin for Clock {
+    fn name(&self) -> &str {
+        "clock"
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn call(&self, _args: Vec<Value>) -> Result<Value, Error> {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock is before the unix epoch")
+            .as_secs_f64();
+        Ok(Value::Number(seconds))
+    }
+}
+
+pub fn registry() -> Vec<&'static dyn Builtin> {
+    vec![&Clock]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_name_and_arity() {
+        assert_eq!(Clock.name(), "clock");
+        assert_eq!(Clock.arity(), 0);
+    }
+
+    #[test]
+    fn test_clock_call_returns_a_number() {
+        let value = Clock.call(Vec::new()).unwrap();
+        assert!(matches!(value, Value::Number(_)));
+    }
+
+    #[test]
+    fn test_registry_contains_clock() {
+        let builtins = registry();
+        assert!(builtins.iter().any(|builtin| builtin.name() == "clock"));
+    }
+}
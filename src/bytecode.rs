@@ -0,0 +1,871 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub mod interner;
+
+use crate::lexer::{Literal, Token, TokenType};
+use crate::utils::{Error, ErrorKind};
+use interner::{InternedStr, Interner};
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Nil,
+    Bool(bool),
+    Number(f64),
+    Str(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum OpCode {
+    Constant,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Negate,
+    Nil,
+    True,
+    False,
+    Not,
+    Equal,
+    Greater,
+    Less,
+    Print,
+    Pop,
+    DefineGlobal,
+    GetGlobal,
+    SetGlobal,
+    Return,
+}
+
+impl OpCode {
+    fn from_byte(byte: u8) -> OpCode {
+        match byte {
+            0 => OpCode::Constant,
+            1 => OpCode::Add,
+            2 => OpCode::Subtract,
+            3 => OpCode::Multiply,
+            4 => OpCode::Divide,
+            5 => OpCode::Negate,
+            6 => OpCode::Nil,
+            7 => OpCode::True,
+            8 => OpCode::False,
+            9 => OpCode::Not,
+            10 => OpCode::Equal,
+            11 => OpCode::Greater,
+            12 => OpCode::Less,
+            13 => OpCode::Print,
+            14 => OpCode::Pop,
+            15 => OpCode::DefineGlobal,
+            16 => OpCode::GetGlobal,
+            17 => OpCode::SetGlobal,
+            18 => OpCode::Return,
+            _ => unreachable!("unknown opcode byte {}", byte),
+        }
+    }
+}
+
+pub struct Chunk {
+    code: Vec<u8>,
+    constants: Vec<Value>,
+    lines: Vec<u32>,
+}
+
+impl Chunk {
+    pub fn new() -> Chunk {
+        Chunk {
+            code: Vec::new(),
+            constants: Vec::new(),
+            lines: Vec::new(),
+        }
+    }
+
+    fn write(&mut self, byte: u8, line: u32) {
+        self.code.push(byte);
+        self.lines.push(line);
+    }
+
+    fn write_op(&mut self, op: OpCode, line: u32) {
+        self.write(op as u8, line);
+    }
+
+    fn add_constant(&mut self, value: Value) -> usize {
+        self.constants.push(value);
+        self.constants.len() - 1
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum Precedence {
+    None,
+    Assignment,
+    Or,
+    And,
+    Equality,
+    Comparison,
+    Term,
+    Factor,
+    Unary,
+    Call,
+    Primary,
+}
+
+impl Precedence {
+    fn next(self) -> Precedence {
+        match self {
+            Precedence::None => Precedence::Assignment,
+            Precedence::Assignment => Precedence::Or,
+            Precedence::Or => Precedence::And,
+            Precedence::And => Precedence::Equality,
+            Precedence::Equality => Precedence::Comparison,
+            Precedence::Comparison => Precedence::Term,
+            Precedence::Term => Precedence::Factor,
+            Precedence::Factor => Precedence::Unary,
+            Precedence::Unary => Precedence::Call,
+            Precedence::Call => Precedence::Primary,
+            Precedence::Primary => Precedence::Primary,
+        }
+    }
+}
+
+type ParseFn = fn(&mut Compiler, bool) -> Result<(), Error>;
+
+struct ParseRule {
+    prefix: Option<ParseFn>,
+    infix: Option<ParseFn>,
+    precedence: Precedence,
+}
+
+fn get_rule(token_type: &TokenType) -> ParseRule {
+    match token_type {
+        TokenType::LeftParen => ParseRule {
+            prefix: Some(grouping),
+            infix: None,
+            precedence: Precedence::None,
+        },
+        TokenType::Minus => ParseRule {
+            prefix: Some(unary),
+            infix: Some(binary),
+            precedence: Precedence::Term,
+        },
+        TokenType::Plus => ParseRule {
+            prefix: None,
+            infix: Some(binary),
+            precedence: Precedence::Term,
+        },
+        TokenType::Slash => ParseRule {
+            prefix: None,
+            infix: Some(binary),
+            precedence: Precedence::Factor,
+        },
+        TokenType::Star => ParseRule {
+            prefix: None,
+            infix: Some(binary),
+            precedence: Precedence::Factor,
+        },
+        TokenType::Bang => ParseRule {
+            prefix: Some(unary),
+            infix: None,
+            precedence: Precedence::None,
+        },
+        TokenType::BangEqual => ParseRule {
+            prefix: None,
+            infix: Some(binary),
+            precedence: Precedence::Equality,
+        },
+        TokenType::EqualEqual => ParseRule {
+            prefix: None,
+            infix: Some(binary),
+            precedence: Precedence::Equality,
+        },
+        TokenType::Greater => ParseRule {
+            prefix: None,
+            infix: Some(binary),
+            precedence: Precedence::Comparison,
+        },
+        TokenType::GreaterEqual => ParseRule {
+            prefix: None,
+            infix: Some(binary),
+            precedence: Precedence::Comparison,
+        },
+        TokenType::Less => ParseRule {
+            prefix: None,
+            infix: Some(binary),
+            precedence: Precedence::Comparison,
+        },
+        TokenType::LessEqual => ParseRule {
+            prefix: None,
+            infix: Some(binary),
+            precedence: Precedence::Comparison,
+        },
+        TokenType::Identifier => ParseRule {
+            prefix: Some(variable),
+            infix: None,
+            precedence: Precedence::None,
+        },
+        TokenType::String => ParseRule {
+            prefix: Some(string),
+            infix: None,
+            precedence: Precedence::None,
+        },
+        TokenType::Number => ParseRule {
+            prefix: Some(number),
+            infix: None,
+            precedence: Precedence::None,
+        },
+        TokenType::False | TokenType::True | TokenType::Nil => ParseRule {
+            prefix: Some(literal),
+            infix: None,
+            precedence: Precedence::None,
+        },
+        _ => ParseRule {
+            prefix: None,
+            infix: None,
+            precedence: Precedence::None,
+        },
+    }
+}
+
+pub struct Compiler {
+    tokens: Vec<Token>,
+    current: usize,
+    chunk: Chunk,
+    errors: Vec<Error>,
+    interner: Rc<Interner>,
+}
+
+impl Compiler {
+    fn new(tokens: Vec<Token>, interner: Rc<Interner>) -> Compiler {
+        Compiler {
+            tokens,
+            current: 0,
+            chunk: Chunk::new(),
+            errors: Vec::new(),
+            interner,
+        }
+    }
+
+    fn compile(mut self) -> Result<Chunk, Vec<Error>> {
+        while !self.is_at_end() {
+            self.declaration();
+        }
+
+        let line = self.previous_line();
+        self.emit_op(OpCode::Return, line);
+
+        if self.errors.is_empty() {
+            Ok(self.chunk)
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    fn declaration(&mut self) {
+        let result = if self.match_token(&[TokenType::Var]) {
+            self.var_declaration()
+        } else {
+            self.statement()
+        };
+
+        if let Err(error) = result {
+            self.errors.push(error);
+            self.synchronize();
+        }
+    }
+
+    fn var_declaration(&mut self) -> Result<(), Error> {
+        let name = self.consume(
+            TokenType::Identifier,
+            ErrorKind::ExpectedToken(String::from("Expected variable name")),
+        )?;
+        let id = self.identifier_constant(&name);
+
+        if self.match_token(&[TokenType::Equal]) {
+            self.expression()?;
+        } else {
+            self.emit_op(OpCode::Nil, name.line);
+        }
+
+        self.consume(TokenType::Semicolon, ErrorKind::ExpectedSemicolon)?;
+        self.emit_global_op(OpCode::DefineGlobal, id, name.line)?;
+        Ok(())
+    }
+
+    fn statement(&mut self) -> Result<(), Error> {
+        if self.match_token(&[TokenType::Print]) {
+            self.print_statement()
+        } else if let Some(name) = unsupported_statement_name(&self.peek().token_type) {
+            Err(self.error(
+                self.peek(),
+                ErrorKind::ExpectedToken(format!(
+                    "'{}' statements are not yet supported by the bytecode engine; use --engine treewalk",
+                    name
+                )),
+            ))
+        } else {
+            self.expression_statement()
+        }
+    }
+
+    fn print_statement(&mut self) -> Result<(), Error> {
+        self.expression()?;
+        self.consume(TokenType::Semicolon, ErrorKind::ExpectedSemicolon)?;
+        let line = self.previous_line();
+        self.emit_op(OpCode::Print, line);
+        Ok(())
+    }
+
+    fn expression_statement(&mut self) -> Result<(), Error> {
+        self.expression()?;
+        self.consume(TokenType::Semicolon, ErrorKind::ExpectedSemicolon)?;
+        let line = self.previous_line();
+        self.emit_op(OpCode::Pop, line);
+        Ok(())
+    }
+
+    fn expression(&mut self) -> Result<(), Error> {
+        self.parse_precedence(Precedence::Assignment)
+    }
+
+    fn parse_precedence(&mut self, precedence: Precedence) -> Result<(), Error> {
+        self.advance();
+        let can_assign = precedence <= Precedence::Assignment;
+
+        match get_rule(&self.previous().token_type).prefix {
+            Some(prefix) => prefix(self, can_assign)?,
+            None => return Err(self.error(self.previous(), ErrorKind::ExpectedExpression)),
+        }
+
+        while precedence <= get_rule(&self.peek().token_type).precedence {
+            self.advance();
+            if let Some(infix) = get_rule(&self.previous().token_type).infix {
+                infix(self, can_assign)?;
+            }
+        }
+
+        if can_assign && self.match_token(&[TokenType::Equal]) {
+            return Err(self.error(self.previous(), ErrorKind::InvalidAssignmentTarget));
+        }
+
+        Ok(())
+    }
+
+    fn identifier_constant(&self, name: &Token) -> InternedStr {
+        match &name.literal {
+            Some(Literal::Identifier(id)) => *id,
+            _ => unreachable!("identifier token missing interned literal"),
+        }
+    }
+
+    fn emit_op(&mut self, op: OpCode, line: u32) {
+        self.chunk.write_op(op, line);
+    }
+
+    fn emit_op_with_operand(&mut self, op: OpCode, operand: usize, line: u32) -> Result<(), Error> {
+        if operand > u16::MAX as usize {
+            return Err(Error {
+                kind: ErrorKind::CompileLimitExceeded(String::from(
+                    "Too many constants in one chunk",
+                )),
+                line,
+            });
+        }
+
+        self.chunk.write_op(op, line);
+        let bytes = (operand as u16).to_le_bytes();
+        self.chunk.write(bytes[0], line);
+        self.chunk.write(bytes[1], line);
+        Ok(())
+    }
+
+    fn emit_global_op(&mut self, op: OpCode, name: InternedStr, line: u32) -> Result<(), Error> {
+        let id = name.as_u32();
+        if id > u16::MAX as u32 {
+            return Err(Error {
+                kind: ErrorKind::CompileLimitExceeded(String::from(
+                    "Too many distinct identifiers and string literals in one program",
+                )),
+                line,
+            });
+        }
+
+        self.chunk.write_op(op, line);
+        let bytes = (id as u16).to_le_bytes();
+        self.chunk.write(bytes[0], line);
+        self.chunk.write(bytes[1], line);
+        Ok(())
+    }
+
+    fn emit_constant(&mut self, value: Value, line: u32) -> Result<(), Error> {
+        let index = self.chunk.add_constant(value);
+        self.emit_op_with_operand(OpCode::Constant, index, line)
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.peek().token_type == TokenType::Eof
+    }
+
+    fn peek(&self) -> Token {
+        self.tokens[self.current].clone()
+    }
+
+    fn previous(&self) -> Token {
+        self.tokens[self.current - 1].clone()
+    }
+
+    fn previous_line(&self) -> u32 {
+        self.previous().line
+    }
+
+    fn advance(&mut self) -> Token {
+        if !self.is_at_end() {
+            self.current += 1;
+        }
+        self.previous()
+    }
+
+    fn check(&self, token_type: TokenType) -> bool {
+        if self.is_at_end() {
+            false
+        } else {
+            self.peek().token_type == token_type
+        }
+    }
+
+    fn match_token(&mut self, types: &[TokenType]) -> bool {
+        for token_type in types {
+            if self.check(token_type.clone()) {
+                self.advance();
+                return true;
+            }
+        }
+        false
+    }
+
+    fn consume(&mut self, token_type: TokenType, kind: ErrorKind) -> Result<Token, Error> {
+        if self.check(token_type) {
+            Ok(self.advance())
+        } else {
+            Err(self.error(self.peek(), kind))
+        }
+    }
+
+    fn error(&self, token: Token, kind: ErrorKind) -> Error {
+        Error {
+            kind,
+            line: token.line,
+        }
+    }
+
+    fn synchronize(&mut self) {
+        self.advance();
+
+        while !self.is_at_end() {
+            if self.previous().token_type == TokenType::Semicolon {
+                return;
+            }
+
+            match self.peek().token_type {
+                TokenType::Class
+                | TokenType::Fun
+                | TokenType::Var
+                | TokenType::For
+                | TokenType::If
+                | TokenType::While
+                | TokenType::Print
+                | TokenType::Return => return,
+                _ => {
+                    self.advance();
+                }
+            }
+        }
+    }
+}
+
+fn unsupported_statement_name(token_type: &TokenType) -> Option<&'static str> {
+    match token_type {
+        TokenType::If => Some("if"),
+        TokenType::While => Some("while"),
+        TokenType::For => Some("for"),
+        TokenType::LeftBracket => Some("{ } block"),
+        TokenType::Fun => Some("fun"),
+        TokenType::Return => Some("return"),
+        TokenType::Class => Some("class"),
+        _ => None,
+    }
+}
+
+fn grouping(compiler: &mut Compiler, _can_assign: bool) -> Result<(), Error> {
+    compiler.expression()?;
+    compiler.consume(
+        TokenType::RightParen,
+        ErrorKind::ExpectedToken(String::from("Expected ')' after expression")),
+    )?;
+    Ok(())
+}
+
+fn unary(compiler: &mut Compiler, _can_assign: bool) -> Result<(), Error> {
+    let operator = compiler.previous();
+    compiler.parse_precedence(Precedence::Unary)?;
+
+    match operator.token_type {
+        TokenType::Minus => compiler.emit_op(OpCode::Negate, operator.line),
+        TokenType::Bang => compiler.emit_op(OpCode::Not, operator.line),
+        _ => unreachable!("unsupported unary operator"),
+    }
+    Ok(())
+}
+
+fn binary(compiler: &mut Compiler, _can_assign: bool) -> Result<(), Error> {
+    let operator = compiler.previous();
+    let rule = get_rule(&operator.token_type);
+    compiler.parse_precedence(rule.precedence.next())?;
+
+    match operator.token_type {
+        TokenType::Plus => compiler.emit_op(OpCode::Add, operator.line),
+        TokenType::Minus => compiler.emit_op(OpCode::Subtract, operator.line),
+        TokenType::Star => compiler.emit_op(OpCode::Multiply, operator.line),
+        TokenType::Slash => compiler.emit_op(OpCode::Divide, operator.line),
+        TokenType::EqualEqual => compiler.emit_op(OpCode::Equal, operator.line),
+        TokenType::BangEqual => {
+            compiler.emit_op(OpCode::Equal, operator.line);
+            compiler.emit_op(OpCode::Not, operator.line);
+        }
+        TokenType::Greater => compiler.emit_op(OpCode::Greater, operator.line),
+        TokenType::GreaterEqual => {
+            compiler.emit_op(OpCode::Less, operator.line);
+            compiler.emit_op(OpCode::Not, operator.line);
+        }
+        TokenType::Less => compiler.emit_op(OpCode::Less, operator.line),
+        TokenType::LessEqual => {
+            compiler.emit_op(OpCode::Greater, operator.line);
+            compiler.emit_op(OpCode::Not, operator.line);
+        }
+        _ => unreachable!("unsupported binary operator"),
+    }
+    Ok(())
+}
+
+fn number(compiler: &mut Compiler, _can_assign: bool) -> Result<(), Error> {
+    let token = compiler.previous();
+    let value = match &token.literal {
+        Some(Literal::Num(n)) => Value::Number(*n),
+        _ => Value::Nil,
+    };
+    compiler.emit_constant(value, token.line)
+}
+
+fn string(compiler: &mut Compiler, _can_assign: bool) -> Result<(), Error> {
+    let token = compiler.previous();
+    let value = match &token.literal {
+        Some(Literal::Str(id)) => Value::Str(compiler.interner.lookup(*id).to_string()),
+        _ => Value::Nil,
+    };
+    compiler.emit_constant(value, token.line)
+}
+
+fn literal(compiler: &mut Compiler, _can_assign: bool) -> Result<(), Error> {
+    let token = compiler.previous();
+    match token.token_type {
+        TokenType::False => compiler.emit_op(OpCode::False, token.line),
+        TokenType::True => compiler.emit_op(OpCode::True, token.line),
+        TokenType::Nil => compiler.emit_op(OpCode::Nil, token.line),
+        _ => unreachable!("unsupported literal"),
+    }
+    Ok(())
+}
+
+fn variable(compiler: &mut Compiler, can_assign: bool) -> Result<(), Error> {
+    let name = compiler.previous();
+    let id = compiler.identifier_constant(&name);
+
+    if can_assign && compiler.match_token(&[TokenType::Equal]) {
+        compiler.expression()?;
+        compiler.emit_global_op(OpCode::SetGlobal, id, name.line)?;
+    } else {
+        compiler.emit_global_op(OpCode::GetGlobal, id, name.line)?;
+    }
+
+    Ok(())
+}
+
+pub struct VM {
+    chunk: Chunk,
+    ip: usize,
+    stack: Vec<Value>,
+    globals: HashMap<InternedStr, Value>,
+    interner: Rc<Interner>,
+}
+
+impl VM {
+    pub fn new(chunk: Chunk, interner: Rc<Interner>) -> VM {
+        VM {
+            chunk,
+            ip: 0,
+            stack: Vec::new(),
+            globals: HashMap::new(),
+            interner,
+        }
+    }
+
+    pub fn run(&mut self) -> Result<(), Error> {
+        loop {
+            let instruction = self.read_byte();
+            let line = self.current_line();
+
+            match OpCode::from_byte(instruction) {
+                OpCode::Constant => {
+                    let index = self.read_u16() as usize;
+                    self.stack.push(self.chunk.constants[index].clone());
+                }
+                OpCode::Nil => self.stack.push(Value::Nil),
+                OpCode::True => self.stack.push(Value::Bool(true)),
+                OpCode::False => self.stack.push(Value::Bool(false)),
+                OpCode::Pop => {
+                    self.pop();
+                }
+                OpCode::Add => self.binary_op(line, |a, b| match (a, b) {
+                    (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a + b)),
+                    (Value::Str(a), Value::Str(b)) => Ok(Value::Str(a + &b)),
+                    _ => Err(ErrorKind::TypeError(String::from(
+                        "Operands must be two numbers or two strings",
+                    ))),
+                })?,
+                OpCode::Subtract => {
+                    self.binary_op(line, |a, b| numeric_op(a, b, |x, y| x - y))?
+                }
+                OpCode::Multiply => {
+                    self.binary_op(line, |a, b| numeric_op(a, b, |x, y| x * y))?
+                }
+                OpCode::Divide => self.binary_op(line, |a, b| numeric_op(a, b, |x, y| x / y))?,
+                OpCode::Greater => {
+                    self.binary_op(line, |a, b| comparison_op(a, b, |x, y| x > y))?
+                }
+                OpCode::Less => self.binary_op(line, |a, b| comparison_op(a, b, |x, y| x < y))?,
+                OpCode::Equal => {
+                    let b = self.pop();
+                    let a = self.pop();
+                    self.stack.push(Value::Bool(values_equal(&a, &b)));
+                }
+                OpCode::Not => {
+                    let value = self.pop();
+                    self.stack.push(Value::Bool(!is_truthy(&value)));
+                }
+                OpCode::Negate => match self.pop() {
+                    Value::Number(n) => self.stack.push(Value::Number(-n)),
+                    _ => {
+                        return Err(Error {
+                            kind: ErrorKind::TypeError(String::from("Operand must be a number")),
+                            line,
+                        })
+                    }
+                },
+                OpCode::Print => {
+                    let value = self.pop();
+                    println!("{}", stringify(&value));
+                }
+                OpCode::DefineGlobal => {
+                    let name = self.read_global_name();
+                    let value = self.pop();
+                    self.globals.insert(name, value);
+                }
+                OpCode::GetGlobal => {
+                    let name = self.read_global_name();
+                    match self.globals.get(&name) {
+                        Some(value) => self.stack.push(value.clone()),
+                        None => {
+                            return Err(Error {
+                                kind: ErrorKind::UndefinedVariable(
+                                    self.interner.lookup(name).to_string(),
+                                ),
+                                line,
+                            })
+                        }
+                    }
+                }
+                OpCode::SetGlobal => {
+                    let name = self.read_global_name();
+                    if !self.globals.contains_key(&name) {
+                        return Err(Error {
+                            kind: ErrorKind::UndefinedVariable(
+                                self.interner.lookup(name).to_string(),
+                            ),
+                            line,
+                        });
+                    }
+                    let value = self.stack.last().expect("stack underflow").clone();
+                    self.globals.insert(name, value);
+                }
+                OpCode::Return => return Ok(()),
+            }
+        }
+    }
+
+    fn read_byte(&mut self) -> u8 {
+        let byte = self.chunk.code[self.ip];
+        self.ip += 1;
+        byte
+    }
+
+    fn read_u16(&mut self) -> u16 {
+        let lo = self.read_byte();
+        let hi = self.read_byte();
+        u16::from_le_bytes([lo, hi])
+    }
+
+    fn current_line(&self) -> u32 {
+        self.chunk.lines[self.ip - 1]
+    }
+
+    fn read_global_name(&mut self) -> InternedStr {
+        InternedStr::from_u32(self.read_u16() as u32)
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack.pop().expect("stack underflow")
+    }
+
+    fn binary_op(
+        &mut self,
+        line: u32,
+        op: impl Fn(Value, Value) -> Result<Value, ErrorKind>,
+    ) -> Result<(), Error> {
+        let b = self.pop();
+        let a = self.pop();
+        let value = op(a, b).map_err(|kind| Error { kind, line })?;
+        self.stack.push(value);
+        Ok(())
+    }
+}
+
+fn numeric_op(a: Value, b: Value, f: impl Fn(f64, f64) -> f64) -> Result<Value, ErrorKind> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => Ok(Value::Number(f(a, b))),
+        _ => Err(ErrorKind::TypeError(String::from("Operands must be numbers"))),
+    }
+}
+
+fn comparison_op(a: Value, b: Value, f: impl Fn(f64, f64) -> bool) -> Result<Value, ErrorKind> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => Ok(Value::Bool(f(a, b))),
+        _ => Err(ErrorKind::TypeError(String::from("Operands must be numbers"))),
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    !matches!(value, Value::Nil | Value::Bool(false))
+}
+
+fn values_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Nil, Value::Nil) => true,
+        (Value::Bool(a), Value::Bool(b)) => a == b,
+        (Value::Number(a), Value::Number(b)) => a == b,
+        (Value::Str(a), Value::Str(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn stringify(value: &Value) -> String {
+    match value {
+        Value::Nil => String::from("nil"),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Str(s) => s.clone(),
+    }
+}
+
+pub fn compile(tokens: Vec<Token>, interner: Rc<Interner>) -> Result<Chunk, Vec<Error>> {
+    Compiler::new(tokens, interner).compile()
+}
+
+pub fn run(source: String) {
+    let (tokens, interner) = match crate::lexer::get_tokens(source) {
+        Ok(result) => result,
+        Err(errors) => {
+            for error in errors.iter() {
+                error.report();
+            }
+            return;
+        }
+    };
+
+    let chunk = match compile(tokens, Rc::clone(&interner)) {
+        Ok(chunk) => chunk,
+        Err(errors) => {
+            for error in errors.iter() {
+                error.report();
+            }
+            return;
+        }
+    };
+
+    if let Err(error) = VM::new(chunk, interner).run() {
+        error.report();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile_and_run(source: &str) -> VM {
+        let (tokens, interner) = crate::lexer::get_tokens(String::from(source)).unwrap();
+        let chunk = compile(tokens, Rc::clone(&interner)).unwrap();
+        let mut vm = VM::new(chunk, interner);
+        vm.run().unwrap();
+        vm
+    }
+
+    fn global_number(vm: &VM, name: &str) -> f64 {
+        for (id, value) in vm.globals.iter() {
+            if vm.interner.lookup(*id) == name {
+                return match value {
+                    Value::Number(n) => *n,
+                    other => panic!("expected a number for '{}', got {:?}", name, other),
+                };
+            }
+        }
+        panic!("global '{}' not found", name);
+    }
+
+    #[test]
+    fn test_vm_executes_arithmetic_and_globals() {
+        let vm = compile_and_run("var result = 1 + 2 * 3; print result;");
+        assert_eq!(global_number(&vm, "result"), 7.0);
+    }
+
+    #[test]
+    fn test_more_than_256_constants_do_not_alias() {
+        let mut source = String::new();
+        for i in 0..300 {
+            source.push_str(&format!("var c{} = {};\n", i, i));
+        }
+        let vm = compile_and_run(&source);
+        assert_eq!(global_number(&vm, "c256"), 256.0);
+    }
+
+    #[test]
+    fn test_more_than_256_globals_do_not_alias() {
+        let mut source = String::new();
+        for i in 0..300 {
+            source.push_str(&format!("var v{} = {};\n", i, i));
+        }
+        source.push_str("var result = v257;\n");
+        let vm = compile_and_run(&source);
+        assert_eq!(global_number(&vm, "result"), 257.0);
+    }
+
+    #[test]
+    fn test_unsupported_control_flow_reports_a_clear_error() {
+        let (tokens, interner) =
+            crate::lexer::get_tokens(String::from("while (true) print 1;")).unwrap();
+        let errors = match compile(tokens, interner) {
+            Err(errors) => errors,
+            Ok(_) => panic!("expected compilation to fail"),
+        };
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(&errors[0].kind, ErrorKind::ExpectedToken(message) if message.contains("not yet supported")));
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InternedStr(pub(crate) u32);
+
+pub struct Interner {
+    ids: HashMap<String, u32>,
+    strings: Vec<Box<str>>,
+}
+
+impl InternedStr {
+    pub(crate) fn as_u32(self) -> u32 {
+        self.0
+    }
+
+    pub(crate) fn from_u32(id: u32) -> InternedStr {
+        InternedStr(id)
+    }
+}
+
+impl Interner {
+    pub fn new() -> Interner {
+        Interner {
+            ids: HashMap::new(),
+            strings: Vec::new(),
+        }
+    }
+
+    pub fn intern(&mut self, text: &str) -> InternedStr {
+        if let Some(&id) = self.ids.get(text) {
+            return InternedStr(id);
+        }
+
+        let id = self.strings.len() as u32;
+        self.strings.push(Box::from(text));
+        self.ids.insert(text.to_string(), id);
+        InternedStr(id)
+    }
+
+    pub fn lookup(&self, interned: InternedStr) -> &str {
+        &self.strings[interned.0 as usize]
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, text: &str) -> Option<InternedStr> {
+        self.ids.get(text).map(|&id| InternedStr(id))
+    }
+}
+
+impl Default for Interner {
+    fn default() -> Interner {
+        Interner::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_same_text_yields_same_handle() {
+        let mut interner = Interner::new();
+        let a = interner.intern("hello");
+        let b = interner.intern("hello");
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_intern_distinct_text_yields_distinct_handles() {
+        let mut interner = Interner::new();
+        let a = interner.intern("hello");
+        let b = interner.intern("world");
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_lookup_round_trips() {
+        let mut interner = Interner::new();
+        let id = interner.intern("hello");
+        assert_eq!(interner.lookup(id), "hello");
+    }
+
+    #[test]
+    fn test_get_finds_an_already_interned_string() {
+        let mut interner = Interner::new();
+        let id = interner.intern("hello");
+        assert_eq!(interner.get("hello"), Some(id));
+    }
+
+    #[test]
+    fn test_get_returns_none_for_an_unseen_string() {
+        let interner = Interner::new();
+        assert_eq!(interner.get("hello"), None);
+    }
+}
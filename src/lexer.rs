@@ -1,6 +1,9 @@
-use crate::utils::Error;
+use std::rc::Rc;
 
-#[derive(Debug, PartialEq)]
+use crate::bytecode::interner::{InternedStr, Interner};
+use crate::utils::{Error, ErrorKind};
+
+#[derive(Debug, Clone, PartialEq)]
 #[allow(dead_code)]
 pub enum TokenType {
     // Single-character tokens.
@@ -55,20 +58,20 @@ pub enum TokenType {
     Eof,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 #[allow(dead_code)]
 pub enum Literal {
-    Identifier(String),
-    Str(String),
+    Identifier(InternedStr),
+    Str(InternedStr),
     Num(f64),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Token {
-    token_type: TokenType,
-    lexeme: String,
-    literal: Option<Literal>,
-    line: u32,
+    pub(crate) token_type: TokenType,
+    pub(crate) lexeme: String,
+    pub(crate) literal: Option<Literal>,
+    pub(crate) line: u32,
 }
 
 impl Token {
@@ -86,6 +89,7 @@ pub struct Scanner {
     source: Vec<char>,
     pub tokens: Vec<Token>,
     pub errors: Vec<Error>,
+    pub interner: Interner,
     current_line: u32,
     lexeme_start_idx: usize,
     current_idx: usize,
@@ -97,6 +101,7 @@ impl Scanner {
             source: Vec::new(),
             tokens: Vec::new(),
             errors: Vec::new(),
+            interner: Interner::new(),
             current_line: 1,
             lexeme_start_idx: 0,
             current_idx: 0,
@@ -117,6 +122,18 @@ impl Scanner {
     fn scan_single_token(&mut self) {
         let token = self.advance();
 
+        if token == '"' {
+            return self.scan_string();
+        }
+
+        if token.is_ascii_digit() {
+            return self.scan_number();
+        }
+
+        if token.is_alphabetic() || token == '_' {
+            return self.scan_identifier();
+        }
+
         // normal tokens
         if let Some(token_type) = match token {
             '(' => Some(TokenType::LeftParen),
@@ -170,15 +187,7 @@ impl Scanner {
             }
             _ => None,
         } {
-            self.tokens.push(Token {
-                token_type,
-                lexeme: self.source[self.lexeme_start_idx..self.current_idx]
-                    .iter()
-                    .cloned()
-                    .collect(),
-                literal: None,
-                line: self.current_line,
-            });
+            self.push_token(token_type, None);
         } else {
             // whitespace tokens
             match token {
@@ -189,7 +198,7 @@ impl Scanner {
                 // treat comments like whitespace
                 '/' => (),
                 _ => self.errors.push(Error {
-                    message: String::from("Unexpected character"),
+                    kind: ErrorKind::UnexpectedChar(token),
                     line: self.current_line,
                 }),
             }
@@ -204,6 +213,96 @@ impl Scanner {
         }
     }
 
+    fn peek_next(&self) -> char {
+        if self.current_idx + 1 >= self.source.len() {
+            '\0'
+        } else {
+            self.source[self.current_idx + 1]
+        }
+    }
+
+    fn scan_string(&mut self) {
+        while self.peek() != '"' && !self.exhausted_chars() {
+            if self.peek() == '\n' {
+                self.current_line += 1;
+            }
+            self.advance();
+        }
+
+        if self.exhausted_chars() {
+            self.errors.push(Error {
+                kind: ErrorKind::UnterminatedString,
+                line: self.current_line,
+            });
+            return;
+        }
+
+        // consume the closing "
+        self.advance();
+
+        let value: String = self.source[self.lexeme_start_idx + 1..self.current_idx - 1]
+            .iter()
+            .cloned()
+            .collect();
+        let interned = self.interner.intern(&value);
+
+        self.push_token(TokenType::String, Some(Literal::Str(interned)));
+    }
+
+    fn scan_number(&mut self) {
+        while self.peek().is_ascii_digit() {
+            self.advance();
+        }
+
+        if self.peek() == '.' && self.peek_next().is_ascii_digit() {
+            // consume the "."
+            self.advance();
+
+            while self.peek().is_ascii_digit() {
+                self.advance();
+            }
+        }
+
+        let lexeme: String = self.source[self.lexeme_start_idx..self.current_idx]
+            .iter()
+            .cloned()
+            .collect();
+        let value = lexeme.parse::<f64>().unwrap();
+
+        self.push_token(TokenType::Number, Some(Literal::Num(value)));
+    }
+
+    fn scan_identifier(&mut self) {
+        while self.peek().is_alphanumeric() || self.peek() == '_' {
+            self.advance();
+        }
+
+        let text: String = self.source[self.lexeme_start_idx..self.current_idx]
+            .iter()
+            .cloned()
+            .collect();
+
+        match keyword_type(&text) {
+            Some(token_type) => self.push_token(token_type, None),
+            None => {
+                let interned = self.interner.intern(&text);
+                self.push_token(TokenType::Identifier, Some(Literal::Identifier(interned)));
+            }
+        }
+    }
+
+    fn push_token(&mut self, token_type: TokenType, literal: Option<Literal>) {
+        self.tokens.push(Token {
+            token_type,
+            lexeme: self.source[self.lexeme_start_idx..self.current_idx]
+                .iter()
+                .cloned()
+                .collect(),
+            literal,
+            line: self.current_line,
+        });
+    }
+
     fn advance(&mut self) -> char {
         if self.exhausted_chars() {
             '\0'
@@ -237,7 +336,30 @@ impl Scanner {
     }
 }
 
-pub fn get_tokens(source: String) -> Result<Vec<Token>, Vec<Error>> {
+fn keyword_type(text: &str) -> Option<TokenType> {
+    match text {
+        "and" => Some(TokenType::And),
+        "class" => Some(TokenType::Class),
+        "else" => Some(TokenType::Else),
+        "false" => Some(TokenType::False),
+        "fun" => Some(TokenType::Fun),
+        "for" => Some(TokenType::For),
+        "if" => Some(TokenType::If),
+        "nil" => Some(TokenType::Nil),
+        "or" => Some(TokenType::Or),
+        "print" => Some(TokenType::Print),
+        "return" => Some(TokenType::Return),
+        "super" => Some(TokenType::Super),
+        "this" => Some(TokenType::This),
+        "true" => Some(TokenType::True),
+        "var" => Some(TokenType::Var),
+        "while" => Some(TokenType::While),
+        "lambda" => Some(TokenType::Lambda),
+        _ => None,
+    }
+}
+
+pub fn get_tokens(source: String) -> Result<(Vec<Token>, Rc<Interner>), Vec<Error>> {
     let mut scanner = Scanner::new();
 
     scanner.scan_tokens(source);
@@ -246,7 +368,7 @@ pub fn get_tokens(source: String) -> Result<Vec<Token>, Vec<Error>> {
         scanner.report_errors();
         Err(scanner.errors)
     } else {
-        Ok(scanner.tokens)
+        Ok((scanner.tokens, Rc::new(scanner.interner)))
     }
 }
 
@@ -258,7 +380,7 @@ mod tests {
     #[test]
     fn test_scan_1() {
         let source = String::from("(");
-        let tokens = get_tokens(source).unwrap();
+        let (tokens, _interner) = get_tokens(source).unwrap();
         let expected = vec![
             Token {
                 token_type: TokenType::LeftParen,
@@ -274,7 +396,7 @@ mod tests {
     #[test]
     fn test_scan_2() {
         let source = String::from("()");
-        let tokens = get_tokens(source).unwrap();
+        let (tokens, _interner) = get_tokens(source).unwrap();
         let expected = vec![
             Token {
                 token_type: TokenType::LeftParen,
@@ -300,7 +422,7 @@ mod tests {
 // aaa
 >= /",
         );
-        let tokens = get_tokens(source).unwrap();
+        let (tokens, _interner) = get_tokens(source).unwrap();
         let expected = vec![
             Token {
                 token_type: TokenType::BangEqual,
@@ -332,7 +454,7 @@ mod tests {
 (( )){} // grouping stuff
 >= / // operators",
         );
-        let tokens = get_tokens(source).unwrap();
+        let (tokens, _interner) = get_tokens(source).unwrap();
         let expected = vec![
             Token {
                 token_type: TokenType::LeftParen,
@@ -386,4 +508,84 @@ mod tests {
         ];
         assert_eq!(&tokens[..], &expected[..]);
     }
+
+    #[test]
+    fn test_scan_string() {
+        let source = String::from("\"hello world\"");
+        let (tokens, interner) = get_tokens(source).unwrap();
+        let expected = vec![
+            Token {
+                token_type: TokenType::String,
+                lexeme: String::from("\"hello world\""),
+                literal: Some(Literal::Str(InternedStr(0))),
+                line: 1,
+            },
+            Token::eof(1),
+        ];
+        assert_eq!(interner.lookup(InternedStr(0)), "hello world");
+        assert_eq!(&tokens[..], &expected[..]);
+    }
+
+    #[test]
+    fn test_scan_unterminated_string() {
+        let source = String::from("\"hello");
+        let result = get_tokens(source);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_scan_number() {
+        let source = String::from("123 45.67");
+        let (tokens, _interner) = get_tokens(source).unwrap();
+        let expected = vec![
+            Token {
+                token_type: TokenType::Number,
+                lexeme: String::from("123"),
+                literal: Some(Literal::Num(123.0)),
+                line: 1,
+            },
+            Token {
+                token_type: TokenType::Number,
+                lexeme: String::from("45.67"),
+                literal: Some(Literal::Num(45.67)),
+                line: 1,
+            },
+            Token::eof(1),
+        ];
+        assert_eq!(&tokens[..], &expected[..]);
+    }
+
+    #[test]
+    fn test_scan_identifier_and_keywords() {
+        let source = String::from("var foo = lambda");
+        let (tokens, _interner) = get_tokens(source).unwrap();
+        let expected = vec![
+            Token {
+                token_type: TokenType::Var,
+                lexeme: String::from("var"),
+                literal: None,
+                line: 1,
+            },
+            Token {
+                token_type: TokenType::Identifier,
+                lexeme: String::from("foo"),
+                literal: Some(Literal::Identifier(InternedStr(0))),
+                line: 1,
+            },
+            Token {
+                token_type: TokenType::Equal,
+                lexeme: String::from("="),
+                literal: None,
+                line: 1,
+            },
+            Token {
+                token_type: TokenType::Lambda,
+                lexeme: String::from("lambda"),
+                literal: None,
+                line: 1,
+            },
+            Token::eof(1),
+        ];
+        assert_eq!(&tokens[..], &expected[..]);
+    }
 }
@@ -2,26 +2,53 @@ use std::io;
 use std::io::Write;
 use structopt::StructOpt;
 
+mod builtins;
+mod bytecode;
 mod lexer;
+mod parser;
 mod treewalk;
 mod utils;
 
+#[derive(Debug, PartialEq)]
+enum Engine {
+    TreeWalk,
+    Bytecode,
+}
+
+impl std::str::FromStr for Engine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Engine, String> {
+        match s {
+            "treewalk" => Ok(Engine::TreeWalk),
+            "bytecode" => Ok(Engine::Bytecode),
+            _ => Err(format!("Unknown engine '{}' (expected treewalk or bytecode)", s)),
+        }
+    }
+}
+
 #[derive(StructOpt)]
 struct Args {
     #[structopt(parse(from_os_str))]
     source_path: Option<std::path::PathBuf>,
+
+    // The bytecode engine does not yet support control flow (if/while/for),
+    // blocks, or function declarations; use it only for straight-line
+    // var/print/expression scripts until that lands.
+    #[structopt(long, default_value = "treewalk")]
+    engine: Engine,
 }
 
 fn main() {
     let args = Args::from_args();
 
     match args.source_path {
-        Some(source_path) => run_file(source_path.to_str().unwrap()),
-        _ => run_prompt(),
+        Some(source_path) => run_file(source_path.to_str().unwrap(), &args.engine),
+        _ => run_prompt(&args.engine),
     }
 }
 
-fn run_file(source_path: &str) {
+fn run_file(source_path: &str, engine: &Engine) {
     let result = std::fs::read_to_string(source_path);
     let content = match result {
         Ok(content) => content,
@@ -29,12 +56,12 @@ fn run_file(source_path: &str) {
             panic!("Error reading source {}", error);
         }
     };
-    run(content);
+    run(content, engine);
 }
 
 static PROMPT: &str = ">>>  ";
 
-fn run_prompt() {
+fn run_prompt(engine: &Engine) {
     loop {
         print!("{}", PROMPT);
         io::stdout().flush().unwrap();
@@ -44,7 +71,7 @@ fn run_prompt() {
             if num_bytes == 0 {
                 break;
             }
-            run(input.trim().to_string());
+            run(input.trim().to_string(), engine);
         } else {
             println!("Error parsing");
             break;
@@ -52,6 +79,9 @@ fn run_prompt() {
     }
 }
 
-fn run(source: String) {
-    treewalk::Interpreter::new().run(source);
+fn run(source: String, engine: &Engine) {
+    match engine {
+        Engine::TreeWalk => treewalk::Interpreter::new().run(source),
+        Engine::Bytecode => bytecode::run(source),
+    }
 }
@@ -0,0 +1,597 @@
+use crate::lexer::{Token, TokenType};
+use crate::utils::{Error, ErrorKind};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Binary(Box<Expr>, Token, Box<Expr>),
+    Unary(Token, Box<Expr>),
+    Literal(Token),
+    Grouping(Box<Expr>),
+    Variable(Token),
+    Assign(Token, Box<Expr>),
+    Logical(Box<Expr>, Token, Box<Expr>),
+    Call(Box<Expr>, Token, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Expression(Expr),
+    Print(Expr),
+    Var(Token, Option<Expr>),
+    Block(Vec<Stmt>),
+    If(Expr, Box<Stmt>, Option<Box<Stmt>>),
+    While(Expr, Box<Stmt>),
+    Function(Token, Vec<Token>, Vec<Stmt>),
+    Return(Token, Option<Expr>),
+}
+
+pub struct Parser {
+    tokens: Vec<Token>,
+    current: usize,
+    errors: Vec<Error>,
+}
+
+impl Parser {
+    pub fn new(tokens: Vec<Token>) -> Parser {
+        Parser {
+            tokens,
+            current: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn parse(&mut self) -> Result<Vec<Stmt>, Vec<Error>> {
+        let mut statements = Vec::new();
+
+        while !self.is_at_end() {
+            match self.declaration() {
+                Ok(stmt) => statements.push(stmt),
+                Err(error) => {
+                    self.errors.push(error);
+                    self.synchronize();
+                }
+            }
+        }
+
+        if self.errors.is_empty() {
+            Ok(statements)
+        } else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    fn declaration(&mut self) -> Result<Stmt, Error> {
+        if self.match_token(&[TokenType::Var]) {
+            self.var_declaration()
+        } else if self.match_token(&[TokenType::Fun]) {
+            self.function_declaration("function")
+        } else {
+            self.statement()
+        }
+    }
+
+    fn var_declaration(&mut self) -> Result<Stmt, Error> {
+        let name = self.consume(
+            TokenType::Identifier,
+            ErrorKind::ExpectedToken(String::from("Expected variable name")),
+        )?;
+
+        let initializer = if self.match_token(&[TokenType::Equal]) {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+
+        self.consume(TokenType::Semicolon, ErrorKind::ExpectedSemicolon)?;
+        Ok(Stmt::Var(name, initializer))
+    }
+
+    fn function_declaration(&mut self, kind: &str) -> Result<Stmt, Error> {
+        let name = self.consume(
+            TokenType::Identifier,
+            ErrorKind::ExpectedToken(format!("Expected {} name", kind)),
+        )?;
+        self.consume(
+            TokenType::LeftParen,
+            ErrorKind::ExpectedToken(format!("Expected '(' after {} name", kind)),
+        )?;
+
+        let mut params = Vec::new();
+        if !self.check(TokenType::RightParen) {
+            loop {
+                params.push(self.consume(
+                    TokenType::Identifier,
+                    ErrorKind::ExpectedToken(String::from("Expected parameter name")),
+                )?);
+                if !self.match_token(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+        self.consume(
+            TokenType::RightParen,
+            ErrorKind::ExpectedToken(String::from("Expected ')' after parameters")),
+        )?;
+
+        self.consume(
+            TokenType::LeftBracket,
+            ErrorKind::ExpectedToken(format!("Expected '{{' before {} body", kind)),
+        )?;
+        let body = self.block()?;
+
+        Ok(Stmt::Function(name, params, body))
+    }
+
+    fn statement(&mut self) -> Result<Stmt, Error> {
+        if self.match_token(&[TokenType::Print]) {
+            self.print_statement()
+        } else if self.match_token(&[TokenType::LeftBracket]) {
+            Ok(Stmt::Block(self.block()?))
+        } else if self.match_token(&[TokenType::If]) {
+            self.if_statement()
+        } else if self.match_token(&[TokenType::While]) {
+            self.while_statement()
+        } else if self.match_token(&[TokenType::For]) {
+            self.for_statement()
+        } else if self.match_token(&[TokenType::Return]) {
+            self.return_statement()
+        } else {
+            self.expression_statement()
+        }
+    }
+
+    fn print_statement(&mut self) -> Result<Stmt, Error> {
+        let value = self.expression()?;
+        self.consume(TokenType::Semicolon, ErrorKind::ExpectedSemicolon)?;
+        Ok(Stmt::Print(value))
+    }
+
+    fn return_statement(&mut self) -> Result<Stmt, Error> {
+        let keyword = self.previous();
+
+        let value = if self.check(TokenType::Semicolon) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+
+        self.consume(TokenType::Semicolon, ErrorKind::ExpectedSemicolon)?;
+        Ok(Stmt::Return(keyword, value))
+    }
+
+    fn while_statement(&mut self) -> Result<Stmt, Error> {
+        self.consume(
+            TokenType::LeftParen,
+            ErrorKind::ExpectedToken(String::from("Expected '(' after 'while'")),
+        )?;
+        let condition = self.expression()?;
+        self.consume(
+            TokenType::RightParen,
+            ErrorKind::ExpectedToken(String::from("Expected ')' after condition")),
+        )?;
+        let body = self.statement()?;
+
+        Ok(Stmt::While(condition, Box::new(body)))
+    }
+
+    fn for_statement(&mut self) -> Result<Stmt, Error> {
+        self.consume(
+            TokenType::LeftParen,
+            ErrorKind::ExpectedToken(String::from("Expected '(' after 'for'")),
+        )?;
+
+        let initializer = if self.match_token(&[TokenType::Semicolon]) {
+            None
+        } else if self.match_token(&[TokenType::Var]) {
+            Some(self.var_declaration()?)
+        } else {
+            Some(self.expression_statement()?)
+        };
+
+        let condition = if self.check(TokenType::Semicolon) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+        self.consume(TokenType::Semicolon, ErrorKind::ExpectedSemicolon)?;
+
+        let increment = if self.check(TokenType::RightParen) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+        self.consume(
+            TokenType::RightParen,
+            ErrorKind::ExpectedToken(String::from("Expected ')' after for clauses")),
+        )?;
+
+        let mut body = self.statement()?;
+
+        if let Some(increment) = increment {
+            body = Stmt::Block(vec![body, Stmt::Expression(increment)]);
+        }
+
+        let condition = condition.unwrap_or(Expr::Literal(Token {
+            token_type: TokenType::True,
+            lexeme: String::from("true"),
+            literal: None,
+            line: self.previous().line,
+        }));
+        body = Stmt::While(condition, Box::new(body));
+
+        if let Some(initializer) = initializer {
+            body = Stmt::Block(vec![initializer, body]);
+        }
+
+        Ok(body)
+    }
+
+    fn if_statement(&mut self) -> Result<Stmt, Error> {
+        self.consume(
+            TokenType::LeftParen,
+            ErrorKind::ExpectedToken(String::from("Expected '(' after 'if'")),
+        )?;
+        let condition = self.expression()?;
+        self.consume(
+            TokenType::RightParen,
+            ErrorKind::ExpectedToken(String::from("Expected ')' after if condition")),
+        )?;
+
+        let then_branch = Box::new(self.statement()?);
+        let else_branch = if self.match_token(&[TokenType::Else]) {
+            Some(Box::new(self.statement()?))
+        } else {
+            None
+        };
+
+        Ok(Stmt::If(condition, then_branch, else_branch))
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>, Error> {
+        let mut statements = Vec::new();
+
+        while !self.check(TokenType::RightBracket) && !self.is_at_end() {
+            statements.push(self.declaration()?);
+        }
+
+        self.consume(
+            TokenType::RightBracket,
+            ErrorKind::ExpectedToken(String::from("Expected '}' after block")),
+        )?;
+        Ok(statements)
+    }
+
+    fn expression_statement(&mut self) -> Result<Stmt, Error> {
+        let expr = self.expression()?;
+        self.consume(TokenType::Semicolon, ErrorKind::ExpectedSemicolon)?;
+        Ok(Stmt::Expression(expr))
+    }
+
+    fn expression(&mut self) -> Result<Expr, Error> {
+        self.assignment()
+    }
+
+    fn assignment(&mut self) -> Result<Expr, Error> {
+        let expr = self.or_expr()?;
+
+        if self.match_token(&[TokenType::Equal]) {
+            let equals = self.previous();
+            let value = self.assignment()?;
+
+            return match expr {
+                Expr::Variable(name) => Ok(Expr::Assign(name, Box::new(value))),
+                _ => Err(self.error(equals, ErrorKind::InvalidAssignmentTarget)),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn or_expr(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.and_expr()?;
+
+        while self.match_token(&[TokenType::Or]) {
+            let operator = self.previous();
+            let right = self.and_expr()?;
+            expr = Expr::Logical(Box::new(expr), operator, Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.equality()?;
+
+        while self.match_token(&[TokenType::And]) {
+            let operator = self.previous();
+            let right = self.equality()?;
+            expr = Expr::Logical(Box::new(expr), operator, Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    fn equality(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.comparison()?;
+
+        while self.match_token(&[TokenType::BangEqual, TokenType::EqualEqual]) {
+            let operator = self.previous();
+            let right = self.comparison()?;
+            expr = Expr::Binary(Box::new(expr), operator, Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.term()?;
+
+        while self.match_token(&[
+            TokenType::Greater,
+            TokenType::GreaterEqual,
+            TokenType::Less,
+            TokenType::LessEqual,
+        ]) {
+            let operator = self.previous();
+            let right = self.term()?;
+            expr = Expr::Binary(Box::new(expr), operator, Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    fn term(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.factor()?;
+
+        while self.match_token(&[TokenType::Minus, TokenType::Plus]) {
+            let operator = self.previous();
+            let right = self.factor()?;
+            expr = Expr::Binary(Box::new(expr), operator, Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    fn factor(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.unary()?;
+
+        while self.match_token(&[TokenType::Slash, TokenType::Star]) {
+            let operator = self.previous();
+            let right = self.unary()?;
+            expr = Expr::Binary(Box::new(expr), operator, Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, Error> {
+        if self.match_token(&[TokenType::Bang, TokenType::Minus]) {
+            let operator = self.previous();
+            let right = self.unary()?;
+            return Ok(Expr::Unary(operator, Box::new(right)));
+        }
+
+        self.call()
+    }
+
+    fn call(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.primary()?;
+
+        while self.match_token(&[TokenType::LeftParen]) {
+            expr = self.finish_call(expr)?;
+        }
+
+        Ok(expr)
+    }
+
+    fn finish_call(&mut self, callee: Expr) -> Result<Expr, Error> {
+        let mut arguments = Vec::new();
+
+        if !self.check(TokenType::RightParen) {
+            loop {
+                arguments.push(self.expression()?);
+                if !self.match_token(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+
+        let paren = self.consume(
+            TokenType::RightParen,
+            ErrorKind::ExpectedToken(String::from("Expected ')' after arguments")),
+        )?;
+
+        Ok(Expr::Call(Box::new(callee), paren, arguments))
+    }
+
+    fn primary(&mut self) -> Result<Expr, Error> {
+        if self.match_token(&[
+            TokenType::False,
+            TokenType::True,
+            TokenType::Nil,
+            TokenType::Number,
+            TokenType::String,
+        ]) {
+            return Ok(Expr::Literal(self.previous()));
+        }
+
+        if self.match_token(&[TokenType::Identifier]) {
+            return Ok(Expr::Variable(self.previous()));
+        }
+
+        if self.match_token(&[TokenType::LeftParen]) {
+            let expr = self.expression()?;
+            self.consume(
+                TokenType::RightParen,
+                ErrorKind::ExpectedToken(String::from("Expected ')' after expression")),
+            )?;
+            return Ok(Expr::Grouping(Box::new(expr)));
+        }
+
+        Err(self.error(self.peek(), ErrorKind::ExpectedExpression))
+    }
+
+    fn match_token(&mut self, types: &[TokenType]) -> bool {
+        for token_type in types {
+            if self.check(token_type.clone()) {
+                self.advance();
+                return true;
+            }
+        }
+        false
+    }
+
+    fn consume(&mut self, token_type: TokenType, kind: ErrorKind) -> Result<Token, Error> {
+        if self.check(token_type) {
+            Ok(self.advance())
+        } else {
+            Err(self.error(self.peek(), kind))
+        }
+    }
+
+    fn check(&self, token_type: TokenType) -> bool {
+        if self.is_at_end() {
+            false
+        } else {
+            self.peek().token_type == token_type
+        }
+    }
+
+    fn advance(&mut self) -> Token {
+        if !self.is_at_end() {
+            self.current += 1;
+        }
+        self.previous()
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.peek().token_type == TokenType::Eof
+    }
+
+    fn peek(&self) -> Token {
+        self.tokens[self.current].clone()
+    }
+
+    fn previous(&self) -> Token {
+        self.tokens[self.current - 1].clone()
+    }
+
+    fn error(&self, token: Token, kind: ErrorKind) -> Error {
+        Error {
+            kind,
+            line: token.line,
+        }
+    }
+
+    fn synchronize(&mut self) {
+        self.advance();
+
+        while !self.is_at_end() {
+            if self.previous().token_type == TokenType::Semicolon {
+                return;
+            }
+
+            match self.peek().token_type {
+                TokenType::Class
+                | TokenType::Fun
+                | TokenType::Var
+                | TokenType::For
+                | TokenType::If
+                | TokenType::While
+                | TokenType::Print
+                | TokenType::Return => return,
+                _ => {
+                    self.advance();
+                }
+            }
+        }
+    }
+}
+
+pub fn parse(tokens: Vec<Token>) -> Result<Vec<Stmt>, Vec<Error>> {
+    Parser::new(tokens).parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer;
+
+    fn parse_source(source: &str) -> Result<Vec<Stmt>, Vec<Error>> {
+        let (tokens, _interner) = lexer::get_tokens(String::from(source)).unwrap();
+        parse(tokens)
+    }
+
+    #[test]
+    fn test_parse_var_declaration() {
+        let statements = parse_source("var x = 1;").unwrap();
+        match &statements[..] {
+            [Stmt::Var(name, Some(Expr::Literal(value)))] => {
+                assert_eq!(name.lexeme, "x");
+                assert_eq!(value.lexeme, "1");
+            }
+            other => panic!("unexpected statements: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_binary_precedence() {
+        let statements = parse_source("1 + 2 * 3;").unwrap();
+        match &statements[..] {
+            [Stmt::Expression(Expr::Binary(left, operator, right))] => {
+                assert_eq!(operator.token_type, TokenType::Plus);
+                match &**left {
+                    Expr::Literal(token) => assert_eq!(token.lexeme, "1"),
+                    other => panic!("unexpected left operand: {:?}", other),
+                }
+                match &**right {
+                    Expr::Binary(inner_left, inner_operator, inner_right) => {
+                        assert_eq!(inner_operator.token_type, TokenType::Star);
+                        match (&**inner_left, &**inner_right) {
+                            (Expr::Literal(a), Expr::Literal(b)) => {
+                                assert_eq!(a.lexeme, "2");
+                                assert_eq!(b.lexeme, "3");
+                            }
+                            other => panic!("unexpected operands: {:?}", other),
+                        }
+                    }
+                    other => panic!("unexpected right operand: {:?}", other),
+                }
+            }
+            other => panic!("unexpected statements: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_if_else() {
+        let statements = parse_source("if (true) print 1; else print 2;").unwrap();
+        match &statements[..] {
+            [Stmt::If(_, then_branch, Some(else_branch))] => {
+                assert!(matches!(**then_branch, Stmt::Print(_)));
+                assert!(matches!(**else_branch, Stmt::Print(_)));
+            }
+            other => panic!("unexpected statements: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_function_declaration() {
+        let statements = parse_source("fun add(a, b) { return a + b; }").unwrap();
+        match &statements[..] {
+            [Stmt::Function(name, params, body)] => {
+                assert_eq!(name.lexeme, "add");
+                assert_eq!(params.len(), 2);
+                assert_eq!(params[0].lexeme, "a");
+                assert_eq!(params[1].lexeme, "b");
+                assert!(matches!(body[..], [Stmt::Return(_, Some(_))]));
+            }
+            other => panic!("unexpected statements: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_missing_semicolon_reports_error() {
+        let result = parse_source("var x = 1");
+        assert!(result.is_err());
+    }
+}
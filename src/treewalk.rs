@@ -1,18 +1,544 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::builtins::{self, Builtin};
+use crate::bytecode::interner::{InternedStr, Interner};
 use crate::lexer;
+use crate::lexer::{Literal, Token, TokenType};
+use crate::parser;
+use crate::parser::{Expr, Stmt};
+use crate::utils::{Error, ErrorKind};
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Nil,
+    Bool(bool),
+    Number(f64),
+    Str(String),
+    Callable(Callable),
+}
+
+#[derive(Clone)]
+pub enum Callable {
+    Native(&'static dyn Builtin),
+    Function(Rc<Function>),
+}
+
+impl Callable {
+    fn arity(&self) -> usize {
+        match self {
+            Callable::Native(builtin) => builtin.arity(),
+            Callable::Function(function) => function.params.len(),
+        }
+    }
+
+    fn ptr_eq(&self, other: &Callable) -> bool {
+        match (self, other) {
+            (Callable::Function(a), Callable::Function(b)) => Rc::ptr_eq(a, b),
+            (Callable::Native(a), Callable::Native(b)) => {
+                std::ptr::eq(*a as *const dyn Builtin, *b as *const dyn Builtin)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for Callable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Callable::Native(builtin) => write!(f, "<native fn {}>", builtin.name()),
+            Callable::Function(function) => fmt::Debug::fmt(function, f),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Function {
+    params: Vec<Token>,
+    body: Vec<Stmt>,
+    closure: Rc<RefCell<Environment>>,
+}
+
+#[derive(Debug)]
+pub struct Environment {
+    values: HashMap<InternedStr, Value>,
+    parent: Option<Rc<RefCell<Environment>>>,
+}
+
+impl Environment {
+    pub fn new() -> Environment {
+        Environment {
+            values: HashMap::new(),
+            parent: None,
+        }
+    }
+
+    pub fn with_parent(parent: Rc<RefCell<Environment>>) -> Environment {
+        Environment {
+            values: HashMap::new(),
+            parent: Some(parent),
+        }
+    }
+
+    pub fn define(&mut self, name: InternedStr, value: Value) {
+        self.values.insert(name, value);
+    }
+
+    pub fn get(&self, name: InternedStr, token: &Token) -> Result<Value, Error> {
+        if let Some(value) = self.values.get(&name) {
+            Ok(value.clone())
+        } else if let Some(parent) = &self.parent {
+            parent.borrow().get(name, token)
+        } else {
+            Err(Error {
+                kind: ErrorKind::UndefinedVariable(token.lexeme.clone()),
+                line: token.line,
+            })
+        }
+    }
+
+    pub fn assign(&mut self, name: InternedStr, token: &Token, value: Value) -> Result<(), Error> {
+        if let std::collections::hash_map::Entry::Occupied(mut entry) = self.values.entry(name) {
+            entry.insert(value);
+            Ok(())
+        } else if let Some(parent) = &self.parent {
+            parent.borrow_mut().assign(name, token, value)
+        } else {
+            Err(Error {
+                kind: ErrorKind::UndefinedVariable(token.lexeme.clone()),
+                line: token.line,
+            })
+        }
+    }
+}
+
+// Signals execution of a statement or expression can produce: either a real
+// error, or (once a `return` is hit) an unwind carrying the returned value
+// back up to the enclosing call. Kept local to the tree-walker rather than
+// folded into `utils::ErrorKind`, since that's a shared type other backends
+// also build `Error`s from and has no business knowing about `treewalk::Value`.
+#[derive(Debug)]
+enum Signal {
+    Error(Error),
+    Return(Value, u32),
+}
+
+impl From<Error> for Signal {
+    fn from(error: Error) -> Signal {
+        Signal::Error(error)
+    }
+}
 
 pub struct Interpreter {
     had_error: bool,
+    environment: Rc<RefCell<Environment>>,
+    interner: Rc<Interner>,
 }
 
 impl Interpreter {
     pub fn new() -> Interpreter {
-        Interpreter { had_error: false }
+        Interpreter {
+            had_error: false,
+            environment: Rc::new(RefCell::new(Environment::new())),
+            interner: Rc::new(Interner::default()),
+        }
     }
 
     pub fn run(&mut self, source: String) {
-        match lexer::get_tokens(source) {
-            Ok(tokens) => println!("Parsed {} tokens!", tokens.len()),
-            Err(_errors) => self.had_error = true,
+        let (tokens, mut interner) = match lexer::get_tokens(source) {
+            Ok(result) => result,
+            Err(_errors) => {
+                self.had_error = true;
+                return;
+            }
+        };
+
+        {
+            let interner_mut = Rc::get_mut(&mut interner)
+                .expect("interner is freshly built and not yet shared");
+            for builtin in builtins::registry() {
+                let id = interner_mut.intern(builtin.name());
+                self.environment
+                    .borrow_mut()
+                    .define(id, Value::Callable(Callable::Native(builtin)));
+            }
+        }
+        self.interner = Rc::clone(&interner);
+
+        let statements = match parser::parse(tokens) {
+            Ok(statements) => statements,
+            Err(errors) => {
+                for error in errors.iter() {
+                    error.report();
+                }
+                self.had_error = true;
+                return;
+            }
+        };
+
+        let env = Rc::clone(&self.environment);
+        for statement in statements.iter() {
+            let result = match self.execute(statement, &env, &interner) {
+                Ok(()) => Ok(()),
+                Err(Signal::Error(error)) => Err(error),
+                Err(Signal::Return(_, line)) => Err(Error {
+                    kind: ErrorKind::RuntimeError(String::from("Cannot return from top-level code")),
+                    line,
+                }),
+            };
+
+            if let Err(error) = result {
+                error.report();
+                self.had_error = true;
+                break;
+            }
+        }
+    }
+
+    fn execute_block(
+        &mut self,
+        statements: &[Stmt],
+        env: &Rc<RefCell<Environment>>,
+        interner: &Interner,
+    ) -> Result<(), Signal> {
+        for statement in statements.iter() {
+            self.execute(statement, env, interner)?;
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, stmt: &Stmt, env: &Rc<RefCell<Environment>>, interner: &Interner) -> Result<(), Signal> {
+        match stmt {
+            Stmt::Expression(expr) => {
+                self.evaluate(expr, env, interner)?;
+                Ok(())
+            }
+            Stmt::Print(expr) => {
+                let value = self.evaluate(expr, env, interner)?;
+                println!("{}", stringify(&value));
+                Ok(())
+            }
+            Stmt::Var(name, initializer) => {
+                let value = match initializer {
+                    Some(expr) => self.evaluate(expr, env, interner)?,
+                    None => Value::Nil,
+                };
+                env.borrow_mut().define(identifier_id(name), value);
+                Ok(())
+            }
+            Stmt::Block(statements) => {
+                let block_env = Rc::new(RefCell::new(Environment::with_parent(Rc::clone(env))));
+                self.execute_block(statements, &block_env, interner)
+            }
+            Stmt::If(condition, then_branch, else_branch) => {
+                if is_truthy(&self.evaluate(condition, env, interner)?) {
+                    self.execute(then_branch, env, interner)
+                } else if let Some(else_branch) = else_branch {
+                    self.execute(else_branch, env, interner)
+                } else {
+                    Ok(())
+                }
+            }
+            Stmt::While(condition, body) => {
+                while is_truthy(&self.evaluate(condition, env, interner)?) {
+                    self.execute(body, env, interner)?;
+                }
+                Ok(())
+            }
+            Stmt::Function(name, params, body) => {
+                let function = Function {
+                    params: params.clone(),
+                    body: body.clone(),
+                    closure: Rc::clone(env),
+                };
+                env.borrow_mut().define(
+                    identifier_id(name),
+                    Value::Callable(Callable::Function(Rc::new(function))),
+                );
+                Ok(())
+            }
+            Stmt::Return(keyword, expr) => {
+                let value = match expr {
+                    Some(expr) => self.evaluate(expr, env, interner)?,
+                    None => Value::Nil,
+                };
+                // Not a real error: unwinds the call stack up to `call`,
+                // which catches it and turns it back into the return value.
+                Err(Signal::Return(value, keyword.line))
+            }
         }
     }
+
+    fn evaluate(
+        &mut self,
+        expr: &Expr,
+        env: &Rc<RefCell<Environment>>,
+        interner: &Interner,
+    ) -> Result<Value, Signal> {
+        match expr {
+            Expr::Literal(token) => Ok(literal_value(token, interner)),
+            Expr::Grouping(inner) => self.evaluate(inner, env, interner),
+            Expr::Variable(name) => env.borrow().get(identifier_id(name), name).map_err(Signal::from),
+            Expr::Assign(name, value) => {
+                let value = self.evaluate(value, env, interner)?;
+                env.borrow_mut().assign(identifier_id(name), name, value.clone())?;
+                Ok(value)
+            }
+            Expr::Logical(left, operator, right) => {
+                let left_value = self.evaluate(left, env, interner)?;
+
+                if operator.token_type == TokenType::Or {
+                    if is_truthy(&left_value) {
+                        return Ok(left_value);
+                    }
+                } else if !is_truthy(&left_value) {
+                    return Ok(left_value);
+                }
+
+                self.evaluate(right, env, interner)
+            }
+            Expr::Unary(operator, right) => {
+                let right_value = self.evaluate(right, env, interner)?;
+
+                match operator.token_type {
+                    TokenType::Minus => match right_value {
+                        Value::Number(n) => Ok(Value::Number(-n)),
+                        _ => Err(Error {
+                            kind: ErrorKind::TypeError(String::from("Operand must be a number")),
+                            line: operator.line,
+                        }
+                        .into()),
+                    },
+                    TokenType::Bang => Ok(Value::Bool(!is_truthy(&right_value))),
+                    _ => unreachable!("unsupported unary operator"),
+                }
+            }
+            Expr::Binary(left, operator, right) => {
+                let left_value = self.evaluate(left, env, interner)?;
+                let right_value = self.evaluate(right, env, interner)?;
+                self.evaluate_binary(operator, left_value, right_value)
+            }
+            Expr::Call(callee, paren, arguments) => {
+                let callee_value = self.evaluate(callee, env, interner)?;
+
+                let mut argument_values = Vec::new();
+                for argument in arguments.iter() {
+                    argument_values.push(self.evaluate(argument, env, interner)?);
+                }
+
+                self.call(callee_value, argument_values, paren.line, interner)
+            }
+        }
+    }
+
+    fn evaluate_binary(&mut self, operator: &Token, left: Value, right: Value) -> Result<Value, Signal> {
+        match operator.token_type {
+            TokenType::Minus => numeric_op(operator, left, right, |a, b| Value::Number(a - b)),
+            TokenType::Slash => numeric_op(operator, left, right, |a, b| Value::Number(a / b)),
+            TokenType::Star => numeric_op(operator, left, right, |a, b| Value::Number(a * b)),
+            TokenType::Plus => match (left, right) {
+                (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a + b)),
+                (Value::Str(a), Value::Str(b)) => Ok(Value::Str(a + &b)),
+                _ => Err(Error {
+                    kind: ErrorKind::TypeError(String::from(
+                        "Operands must be two numbers or two strings",
+                    )),
+                    line: operator.line,
+                }
+                .into()),
+            },
+            TokenType::Greater => numeric_op(operator, left, right, |a, b| Value::Bool(a > b)),
+            TokenType::GreaterEqual => numeric_op(operator, left, right, |a, b| Value::Bool(a >= b)),
+            TokenType::Less => numeric_op(operator, left, right, |a, b| Value::Bool(a < b)),
+            TokenType::LessEqual => numeric_op(operator, left, right, |a, b| Value::Bool(a <= b)),
+            TokenType::EqualEqual => Ok(Value::Bool(values_equal(&left, &right))),
+            TokenType::BangEqual => Ok(Value::Bool(!values_equal(&left, &right))),
+            _ => unreachable!("unsupported binary operator"),
+        }
+    }
+
+    fn call(
+        &mut self,
+        callee: Value,
+        arguments: Vec<Value>,
+        line: u32,
+        interner: &Interner,
+    ) -> Result<Value, Signal> {
+        let callable = match callee {
+            Value::Callable(callable) => callable,
+            _ => {
+                return Err(Error {
+                    kind: ErrorKind::RuntimeError(String::from(
+                        "Can only call functions and classes",
+                    )),
+                    line,
+                }
+                .into())
+            }
+        };
+
+        if callable.arity() != arguments.len() {
+            return Err(Error {
+                kind: ErrorKind::RuntimeError(format!(
+                    "Expected {} arguments but got {}",
+                    callable.arity(),
+                    arguments.len()
+                )),
+                line,
+            }
+            .into());
+        }
+
+        let function = match callable {
+            Callable::Native(builtin) => return builtin.call(arguments).map_err(Signal::from),
+            Callable::Function(function) => function,
+        };
+
+        let call_env = Rc::new(RefCell::new(Environment::with_parent(Rc::clone(
+            &function.closure,
+        ))));
+        for (param, argument) in function.params.iter().zip(arguments) {
+            call_env.borrow_mut().define(identifier_id(param), argument);
+        }
+
+        for statement in function.body.iter() {
+            match self.execute(statement, &call_env, interner) {
+                Ok(()) => (),
+                Err(Signal::Return(value, _)) => return Ok(value),
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(Value::Nil)
+    }
+}
+
+fn identifier_id(token: &Token) -> InternedStr {
+    match &token.literal {
+        Some(Literal::Identifier(id)) => *id,
+        _ => unreachable!("identifier token missing interned literal"),
+    }
+}
+
+fn literal_value(token: &Token, interner: &Interner) -> Value {
+    match token.token_type {
+        TokenType::False => Value::Bool(false),
+        TokenType::True => Value::Bool(true),
+        TokenType::Nil => Value::Nil,
+        TokenType::Number => match &token.literal {
+            Some(Literal::Num(n)) => Value::Number(*n),
+            _ => Value::Nil,
+        },
+        TokenType::String => match &token.literal {
+            Some(Literal::Str(id)) => Value::Str(interner.lookup(*id).to_string()),
+            _ => Value::Nil,
+        },
+        _ => Value::Nil,
+    }
+}
+
+fn numeric_op(
+    operator: &Token,
+    left: Value,
+    right: Value,
+    op: fn(f64, f64) -> Value,
+) -> Result<Value, Signal> {
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => Ok(op(a, b)),
+        _ => Err(Error {
+            kind: ErrorKind::TypeError(String::from("Operands must be numbers")),
+            line: operator.line,
+        }
+        .into()),
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    !matches!(value, Value::Nil | Value::Bool(false))
+}
+
+fn values_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Nil, Value::Nil) => true,
+        (Value::Bool(a), Value::Bool(b)) => a == b,
+        (Value::Number(a), Value::Number(b)) => a == b,
+        (Value::Str(a), Value::Str(b)) => a == b,
+        (Value::Callable(a), Value::Callable(b)) => a.ptr_eq(b),
+        _ => false,
+    }
+}
+
+fn stringify(value: &Value) -> String {
+    match value {
+        Value::Nil => String::from("nil"),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Str(s) => s.clone(),
+        Value::Callable(Callable::Native(builtin)) => format!("<native fn {}>", builtin.name()),
+        Value::Callable(Callable::Function(_)) => String::from("<fn>"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(interpreter: &Interpreter, name: &str) -> Value {
+        let id = interpreter
+            .interner
+            .get(name)
+            .unwrap_or_else(|| panic!("'{}' was never interned", name));
+        let name_token = Token {
+            token_type: TokenType::Identifier,
+            lexeme: String::from(name),
+            literal: Some(Literal::Identifier(id)),
+            line: 0,
+        };
+        interpreter.environment.borrow().get(id, &name_token).unwrap()
+    }
+
+    fn number(value: Value) -> f64 {
+        match value {
+            Value::Number(n) => n,
+            other => panic!("expected a number, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_recursive_function_call() {
+        let mut interpreter = Interpreter::new();
+        interpreter.run(String::from(
+            "fun fib(n) { if (n < 2) return n; return fib(n - 1) + fib(n - 2); } \
+             var result = fib(6);",
+        ));
+        assert_eq!(number(lookup(&interpreter, "result")), 8.0);
+    }
+
+    #[test]
+    fn test_closure_captures_enclosing_variable() {
+        let mut interpreter = Interpreter::new();
+        interpreter.run(String::from(
+            "fun make_counter() { \
+                var count = 0; \
+                fun increment() { count = count + 1; return count; } \
+                return increment; \
+             } \
+             var counter = make_counter(); \
+             var a = counter(); \
+             var b = counter();",
+        ));
+        assert_eq!(number(lookup(&interpreter, "a")), 1.0);
+        assert_eq!(number(lookup(&interpreter, "b")), 2.0);
+    }
+
+    #[test]
+    fn test_block_scope_does_not_leak_into_enclosing_scope() {
+        let mut interpreter = Interpreter::new();
+        interpreter.run(String::from(
+            "var x = 1; { var x = 2; } var y = x;",
+        ));
+        assert_eq!(number(lookup(&interpreter, "y")), 1.0);
+    }
 }
@@ -1,11 +1,44 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    UnexpectedChar(char),
+    UnterminatedString,
+    ExpectedExpression,
+    ExpectedSemicolon,
+    ExpectedToken(String),
+    TypeError(String),
+    UndefinedVariable(String),
+    InvalidAssignmentTarget,
+    RuntimeError(String),
+    CompileLimitExceeded(String),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedChar(c) => write!(f, "Unexpected character '{}'", c),
+            ErrorKind::UnterminatedString => write!(f, "Unterminated string"),
+            ErrorKind::ExpectedExpression => write!(f, "Expected expression"),
+            ErrorKind::ExpectedSemicolon => write!(f, "Expected ';'"),
+            ErrorKind::ExpectedToken(message) => write!(f, "{}", message),
+            ErrorKind::TypeError(message) => write!(f, "{}", message),
+            ErrorKind::UndefinedVariable(name) => write!(f, "Undefined variable '{}'", name),
+            ErrorKind::InvalidAssignmentTarget => write!(f, "Invalid assignment target"),
+            ErrorKind::RuntimeError(message) => write!(f, "{}", message),
+            ErrorKind::CompileLimitExceeded(message) => write!(f, "{}", message),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Error {
-    pub message: String,
+    pub kind: ErrorKind,
     pub line: u32,
 }
 
 impl Error {
     pub fn report(&self) {
-        println!("[line {}] {}", self.line, self.message);
+        println!("[line {}] {}", self.line, self.kind);
     }
 }